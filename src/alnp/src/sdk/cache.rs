@@ -0,0 +1,128 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::identity::NodeCredentials;
+use crate::messages::{CapabilitySet, DeviceIdentity};
+
+/// Everything `AlpineClient::connect` needs to skip regenerating identity keys
+/// and attempt a fast session resume, rather than running a full
+/// `X25519KeyExchange` handshake from scratch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedSession {
+    pub credentials: NodeCredentials,
+    pub capabilities: CapabilitySet,
+    pub last_endpoint: SocketAddr,
+    pub resumption_token: Option<Vec<u8>>,
+}
+
+/// Pluggable persistence for [`CachedSession`] material, keyed by `DeviceIdentity`.
+///
+/// [`AlpineClient::connect_cached`](super::client::AlpineClient::connect_cached)
+/// consults this before handshaking and writes the updated state back after a
+/// successful one. Callers that can't or don't want to persist credentials
+/// across restarts (no-std/embedded controllers) simply don't use it — this
+/// whole module is behind the `persistent-cache` feature for that reason.
+pub trait Cache: Send + Sync {
+    fn load(&self, identity: &DeviceIdentity) -> Option<CachedSession>;
+    fn store(&self, identity: &DeviceIdentity, session: &CachedSession);
+}
+
+/// Default [`Cache`]: one JSON file per device under a base directory.
+pub struct FileCache {
+    base_dir: PathBuf,
+}
+
+impl FileCache {
+    pub fn new(base_dir: impl Into<PathBuf>) -> Self {
+        Self { base_dir: base_dir.into() }
+    }
+
+    fn path_for(&self, identity: &DeviceIdentity) -> PathBuf {
+        self.base_dir.join(format!("{}.json", sanitize_component(&identity.device_id)))
+    }
+}
+
+/// Strips `raw` down to a single path component safe to join onto `base_dir`.
+///
+/// `device_id` is attacker-controlled (it comes off the wire during the
+/// handshake, before any credential is verified), so it must never be used
+/// as a path fragment as-is: a value like `../../etc/passwd` would let a
+/// peer read or overwrite arbitrary files outside `base_dir`. Any character
+/// that isn't ASCII alphanumeric, `-`, or `_` — including `/`, `\`, and `.`
+/// — is replaced, which also rules out `.` and `..` components.
+///
+/// Replacing unsafe characters is lossy — `"rig/1"` and `"rig.1"` would
+/// otherwise both sanitize to `"rig_1"` and silently share a cache file. A
+/// hash of the untouched `raw` value is appended so distinct ids never
+/// collide just because they sanitize the same way.
+fn sanitize_component(raw: &str) -> String {
+    use std::hash::{Hash, Hasher};
+
+    let sanitized: String = raw
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let stem = if sanitized.is_empty() { "_" } else { &sanitized };
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    raw.hash(&mut hasher);
+    format!("{stem}-{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sanitize_component;
+
+    #[test]
+    fn passes_through_ordinary_device_ids() {
+        assert!(sanitize_component("controller-07_main").starts_with("controller-07_main-"));
+    }
+
+    #[test]
+    fn neutralizes_parent_directory_traversal() {
+        let sanitized = sanitize_component("../../etc/passwd");
+        assert!(!sanitized.contains('/'));
+        assert!(!sanitized.contains(".."));
+    }
+
+    #[test]
+    fn neutralizes_absolute_paths() {
+        let sanitized = sanitize_component("/etc/passwd");
+        assert!(!sanitized.contains('/'));
+    }
+
+    #[test]
+    fn neutralizes_backslash_traversal() {
+        let sanitized = sanitize_component("..\\..\\windows\\system32");
+        assert!(!sanitized.contains('\\'));
+        assert!(!sanitized.contains(".."));
+    }
+
+    #[test]
+    fn falls_back_to_a_nonempty_name_for_an_all_unsafe_id() {
+        assert!(sanitize_component("../..").starts_with("_____-"));
+        assert!(sanitize_component("").starts_with("_-"));
+    }
+
+    #[test]
+    fn distinguishes_ids_that_sanitize_to_the_same_stem() {
+        assert_ne!(sanitize_component("rig/1"), sanitize_component("rig.1"));
+        assert_ne!(sanitize_component("rig/1"), sanitize_component("rig_1"));
+    }
+}
+
+impl Cache for FileCache {
+    fn load(&self, identity: &DeviceIdentity) -> Option<CachedSession> {
+        let bytes = std::fs::read(self.path_for(identity)).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    fn store(&self, identity: &DeviceIdentity, session: &CachedSession) {
+        let _ = std::fs::create_dir_all(&self.base_dir);
+        if let Ok(bytes) = serde_json::to_vec_pretty(session) {
+            let _ = std::fs::write(self.path_for(identity), bytes);
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+
+use crate::messages::{ChannelFormat, MessageType};
+use crate::stream::FrameTransport;
+
+use super::client::{ClientError, ClientInner};
+use super::mux::MuxedFrameTransport;
+
+/// Numeric id identifying one logical channel within a session.
+pub type ChannelId = u32;
+
+/// Leading bytes written ahead of every channel payload: the channel id and the
+/// channel's own monotonic sequence number, both little-endian.
+const TAG_LEN: usize = 12;
+
+fn encode_tag(channel_id: ChannelId, seq: u64) -> [u8; TAG_LEN] {
+    let mut tag = [0u8; TAG_LEN];
+    tag[0..4].copy_from_slice(&channel_id.to_le_bytes());
+    tag[4..12].copy_from_slice(&seq.to_le_bytes());
+    tag
+}
+
+fn decode_tag(bytes: &[u8]) -> Option<(ChannelId, u64, &[u8])> {
+    if bytes.len() < TAG_LEN {
+        return None;
+    }
+    let channel_id = ChannelId::from_le_bytes(bytes[0..4].try_into().ok()?);
+    let seq = u64::from_le_bytes(bytes[4..12].try_into().ok()?);
+    Some((channel_id, seq, &bytes[TAG_LEN..]))
+}
+
+struct Subscriber {
+    sender: mpsc::UnboundedSender<Vec<u8>>,
+}
+
+/// Multiplexes many logical channels over one established session.
+///
+/// Outgoing frames are tagged with a channel id plus a per-channel monotonic
+/// sequence number; on the receive side, inbound frames are dispatched to the
+/// matching subscriber by id. Every [`ChannelHandle`] it hands out shares the
+/// same underlying socket, session keys, and keepalive, but owns its own
+/// priority, [`ChannelFormat`], and sequence counter, so closing one channel
+/// never disturbs the others.
+pub struct ChannelManager {
+    transport: MuxedFrameTransport,
+    next_id: AtomicU32,
+    subscribers: Arc<Mutex<HashMap<ChannelId, Subscriber>>>,
+    dispatcher: JoinHandle<()>,
+}
+
+impl ChannelManager {
+    /// Builds a manager over `transport`, spawning the dispatcher that drains
+    /// `inbound` (the demultiplexed stream-frame channel from `SocketMux`) and
+    /// routes each frame to its subscriber.
+    pub(crate) fn new(transport: MuxedFrameTransport, mut inbound: mpsc::UnboundedReceiver<Vec<u8>>) -> Arc<Self> {
+        let subscribers: Arc<Mutex<HashMap<ChannelId, Subscriber>>> = Arc::new(Mutex::new(HashMap::new()));
+        let dispatch_subscribers = subscribers.clone();
+        let dispatcher = tokio::spawn(async move {
+            while let Some(datagram) = inbound.recv().await {
+                // `inbound` is `SocketMux`'s demultiplexed stream-frame channel: each
+                // datagram still carries the leading `MessageType::StreamFrame` byte
+                // the mux used to route it here, ahead of the channel tag.
+                let Some(body) = datagram.get(1..) else {
+                    continue;
+                };
+                let Some((channel_id, _seq, payload)) = decode_tag(body) else {
+                    continue;
+                };
+                let guard = dispatch_subscribers.lock().await;
+                if let Some(subscriber) = guard.get(&channel_id) {
+                    let _ = subscriber.sender.send(payload.to_vec());
+                }
+            }
+            // The mux has gone (e.g. replaced by a reconnect): drop every
+            // subscriber so a `recv` already waiting returns `None` once its
+            // queued frames are drained.
+            dispatch_subscribers.lock().await.clear();
+        });
+
+        Arc::new(Self {
+            transport,
+            next_id: AtomicU32::new(0),
+            subscribers,
+            dispatcher,
+        })
+    }
+
+    /// Opens a new logical channel, allocating the next id and registering its
+    /// inbound subscriber. `inner` is the owning client's shared state, kept
+    /// around so the handle can detect a reconnect that has swapped in a new
+    /// `ChannelManager` out from under it (see [`ChannelHandle::send`]).
+    pub async fn open_channel(
+        self: &Arc<Self>,
+        inner: Arc<Mutex<ClientInner>>,
+        format: ChannelFormat,
+        priority: u8,
+    ) -> ChannelHandle {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.subscribers.lock().await.insert(id, Subscriber { sender });
+
+        ChannelHandle {
+            manager: self.clone(),
+            inner,
+            id,
+            format,
+            priority,
+            seq: AtomicU64::new(0),
+            inbound: Mutex::new(receiver),
+        }
+    }
+
+    async fn unsubscribe(&self, id: ChannelId) {
+        self.subscribers.lock().await.remove(&id);
+    }
+}
+
+impl Drop for ChannelManager {
+    fn drop(&mut self) {
+        self.dispatcher.abort();
+    }
+}
+
+/// One logical channel multiplexed over a shared session. Driving a DMX
+/// universe, a pixel-mapped video feed, and a telemetry back-channel
+/// concurrently just means opening three of these against the same client.
+pub struct ChannelHandle {
+    manager: Arc<ChannelManager>,
+    /// The owning client's shared state, used only to detect that a reconnect
+    /// has since swapped in a fresh `ChannelManager` (see [`ChannelHandle::send`]).
+    inner: Arc<Mutex<ClientInner>>,
+    id: ChannelId,
+    format: ChannelFormat,
+    priority: u8,
+    seq: AtomicU64,
+    inbound: Mutex<mpsc::UnboundedReceiver<Vec<u8>>>,
+}
+
+impl ChannelHandle {
+    pub fn id(&self) -> ChannelId {
+        self.id
+    }
+
+    pub fn format(&self) -> &ChannelFormat {
+        &self.format
+    }
+
+    pub fn priority(&self) -> u8 {
+        self.priority
+    }
+
+    /// Sends `payload` tagged with this channel's id and next sequence number.
+    ///
+    /// A reconnect rebuilds the client's session, transport, and
+    /// `ChannelManager` from scratch, which would otherwise leave a handle
+    /// opened before the reconnect silently writing to a manager nobody reads
+    /// from anymore. This checks the handle's manager against the client's
+    /// current one and fails loudly with [`ClientError::StaleChannel`] instead.
+    pub async fn send(&self, payload: &[u8]) -> Result<(), ClientError> {
+        if !Arc::ptr_eq(&self.manager, &self.inner.lock().await.channels) {
+            return Err(ClientError::StaleChannel);
+        }
+        let seq = self.seq.fetch_add(1, Ordering::SeqCst);
+        let mut framed = Vec::with_capacity(1 + TAG_LEN + payload.len());
+        framed.push(MessageType::StreamFrame as u8);
+        framed.extend_from_slice(&encode_tag(self.id, seq));
+        framed.extend_from_slice(payload);
+        self.manager.transport.send_frame(&framed).map_err(ClientError::Io)
+    }
+
+    /// Awaits the next inbound frame addressed to this channel, or `None` once
+    /// the channel has been closed or the handle has gone stale across a
+    /// reconnect (see [`ChannelHandle::send`]) — a stale manager's dispatcher
+    /// never receives new datagrams to route, so without this check a caller
+    /// looping on `recv` would hang forever instead of observing either.
+    ///
+    /// Frames the old dispatcher already queued before the reconnect are
+    /// drained first, so staleness never discards data that arrived in time.
+    pub async fn recv(&self) -> Option<Vec<u8>> {
+        let mut inbound = self.inbound.lock().await;
+        if let Ok(frame) = inbound.try_recv() {
+            return Some(frame);
+        }
+        if !Arc::ptr_eq(&self.manager, &self.inner.lock().await.channels) {
+            return None;
+        }
+        inbound.recv().await
+    }
+
+    /// Closes this channel. Other channels sharing the manager are unaffected.
+    pub async fn close(self) {
+        self.manager.unsubscribe(self.id).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_tag, encode_tag, TAG_LEN};
+
+    #[test]
+    fn round_trips_channel_id_and_seq() {
+        let tag = encode_tag(7, 42);
+        let payload = b"dmx-frame";
+        let mut framed = Vec::new();
+        framed.extend_from_slice(&tag);
+        framed.extend_from_slice(payload);
+
+        let (channel_id, seq, rest) = decode_tag(&framed).expect("tag should decode");
+        assert_eq!(channel_id, 7);
+        assert_eq!(seq, 42);
+        assert_eq!(rest, payload);
+    }
+
+    #[test]
+    fn rejects_short_input() {
+        let short = vec![0u8; TAG_LEN - 1];
+        assert!(decode_tag(&short).is_none());
+    }
+
+    #[test]
+    fn empty_payload_is_valid() {
+        let tag = encode_tag(u32::MAX, u64::MAX);
+        let (channel_id, seq, rest) = decode_tag(&tag).expect("tag-only input should decode");
+        assert_eq!(channel_id, u32::MAX);
+        assert_eq!(seq, u64::MAX);
+        assert!(rest.is_empty());
+    }
+}
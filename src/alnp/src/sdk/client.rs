@@ -1,31 +1,43 @@
 use std::collections::HashMap;
 use std::fmt;
+use std::future::Future;
 use std::net::SocketAddr;
-use std::net::UdpSocket as StdUdpSocket;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, watch, Mutex};
 use tokio::task::JoinHandle;
 
 use crate::crypto::identity::NodeCredentials;
 use crate::crypto::X25519KeyExchange;
 use crate::control::{ControlClient, ControlCrypto};
 use crate::handshake::keepalive;
-use crate::handshake::transport::{CborUdpTransport, TimeoutTransport};
+use crate::handshake::transport::TimeoutTransport;
 use crate::handshake::{ChallengeAuthenticator, HandshakeContext, HandshakeError};
-use crate::messages::{CapabilitySet, ChannelFormat, ControlEnvelope, ControlOp, DeviceIdentity, MessageType};
+use crate::messages::{CapabilitySet, ChannelFormat, ControlEnvelope, ControlOp, DeviceIdentity};
 use crate::session::{AlnpSession, AlnpRole};
-use crate::stream::{AlnpStream, FrameTransport, StreamError};
+use crate::stream::{AlnpStream, StreamError};
 use serde_json::Value;
 use uuid::Uuid;
 
+use super::channel::{ChannelHandle, ChannelManager};
+use super::mux::{MuxedFrameTransport, MuxedHandshakeTransport, SocketMux};
+use super::reconnect::{Backoff, ConnectionState};
+
 /// Errors emitted by the high-level SDK client.
 #[derive(Debug)]
 pub enum ClientError {
     Io(String),
     Handshake(HandshakeError),
     Stream(StreamError),
+    /// Every candidate endpoint was tried and none established a session.
+    AllEndpointsFailed(Vec<(SocketAddr, String)>),
+    /// A [`ChannelHandle`] outlived a reconnect: the session, transport, and
+    /// `ChannelManager` it was opened against have since been replaced, so
+    /// sending on it would silently go nowhere. Open a fresh channel instead.
+    StaleChannel,
 }
 
 impl fmt::Display for ClientError {
@@ -34,6 +46,16 @@ impl fmt::Display for ClientError {
             ClientError::Io(err) => write!(f, "io error: {}", err),
             ClientError::Handshake(err) => write!(f, "handshake error: {}", err),
             ClientError::Stream(err) => write!(f, "stream error: {}", err),
+            ClientError::AllEndpointsFailed(attempts) => {
+                write!(f, "all {} candidate endpoint(s) failed:", attempts.len())?;
+                for (addr, err) in attempts {
+                    write!(f, " [{} -> {}]", addr, err)?;
+                }
+                Ok(())
+            }
+            ClientError::StaleChannel => {
+                write!(f, "channel handle outlived a reconnect; open a new one")
+            }
         }
     }
 }
@@ -56,62 +78,230 @@ impl From<std::io::Error> for ClientError {
     }
 }
 
-/// Thin UDP transport for the ALPINE streaming layer.
-struct UdpFrameTransport {
-    socket: StdUdpSocket,
-    peer: SocketAddr,
+/// A future resolving to an ordered list of candidate endpoints, most-preferred first.
+pub type EndpointResolver =
+    Box<dyn Fn() -> Pin<Box<dyn Future<Output = Vec<SocketAddr>> + Send>> + Send + Sync>;
+
+/// Where `connect` should look for the remote device.
+///
+/// A single address or a fixed list resolve immediately; a [`Resolver`](Endpoints::Resolver)
+/// is invoked lazily at connect time, which is useful for DNS-fronted or multi-homed
+/// controllers whose address set can change between runs.
+pub enum Endpoints {
+    List(Vec<SocketAddr>),
+    Resolver(EndpointResolver),
+}
+
+impl Endpoints {
+    /// Resolves to the current candidate list. Takes `&self` rather than
+    /// consuming it so a [`Resolver`](Endpoints::Resolver) can be re-invoked on
+    /// every reconnect round instead of only once at the initial `connect`.
+    async fn resolve(&self) -> Vec<SocketAddr> {
+        match self {
+            Endpoints::List(addrs) => addrs.clone(),
+            Endpoints::Resolver(resolve) => resolve().await,
+        }
+    }
 }
 
-impl UdpFrameTransport {
-    fn new(local: SocketAddr, peer: SocketAddr) -> Result<Self, std::io::Error> {
-        let socket = StdUdpSocket::bind(local)?;
-        socket.connect(peer)?;
-        Ok(Self { socket, peer })
+impl From<SocketAddr> for Endpoints {
+    fn from(addr: SocketAddr) -> Self {
+        Endpoints::List(vec![addr])
     }
 }
 
-impl FrameTransport for UdpFrameTransport {
-    fn send_frame(&self, bytes: &[u8]) -> Result<(), String> {
-        self.socket
-            .send(bytes)
-            .map_err(|e| format!("udp stream send: {}", e))?;
-        Ok(())
+impl From<Vec<SocketAddr>> for Endpoints {
+    fn from(addrs: Vec<SocketAddr>) -> Self {
+        Endpoints::List(addrs)
     }
 }
 
+/// The live session, transport, and stream state, held behind one lock so a
+/// reconnect can swap all of it out atomically while other calls wait.
+///
+/// Visible to [`super::channel`] so a [`ChannelHandle`] can compare its
+/// `channels` against the client's current one and detect that it has gone
+/// stale across a reconnect.
+pub(crate) struct ClientInner {
+    session: AlnpSession,
+    transport: Arc<Mutex<TimeoutTransport<MuxedHandshakeTransport>>>,
+    stream: AlnpStream<MuxedFrameTransport>,
+    control: ControlClient,
+    pub(crate) channels: Arc<ChannelManager>,
+    keepalive_handle: JoinHandle<()>,
+    remote_addr: SocketAddr,
+    /// The token the peer handed back for fast-resuming this session, if it
+    /// supports that. Carried into the next reconnect attempt so a transient
+    /// drop doesn't always cost a full `X25519KeyExchange` handshake.
+    resumption_token: Option<Vec<u8>>,
+    /// Kept alive only to hold the shared socket's demux reader task open;
+    /// dropping it tears the socket's reader down.
+    _mux: SocketMux,
+}
+
+/// Credentials and identity needed to re-run the handshake on reconnect, kept
+/// alongside the original `Endpoints` source so it can be re-resolved on every
+/// reconnect round rather than retrying whatever candidate list resolved at
+/// the initial `connect`.
+struct ReconnectParams {
+    local_addr: SocketAddr,
+    endpoints: Endpoints,
+    /// The endpoint that most recently established, preferred when the
+    /// endpoints are re-resolved for the next reconnect attempt.
+    preferred: SocketAddr,
+    identity: DeviceIdentity,
+    capabilities: CapabilitySet,
+    credentials: NodeCredentials,
+    loss_tx: mpsc::UnboundedSender<()>,
+}
+
 /// High-level controller client that orchestrates the discovery, handshake, stream,
 /// and keepalive flows.
 pub struct AlpineClient {
-    session: AlnpSession,
-    transport: Arc<Mutex<TimeoutTransport<CborUdpTransport>>>,
-    stream: AlnpStream<UdpFrameTransport>,
-    control: ControlClient,
-    keepalive_handle: Option<JoinHandle<()>>,
+    inner: Arc<Mutex<ClientInner>>,
+    seq_counter: Arc<AtomicU64>,
+    state_tx: watch::Sender<ConnectionState>,
+    state_rx: watch::Receiver<ConnectionState>,
+    supervisor_handle: Option<JoinHandle<()>>,
 }
 
 impl AlpineClient {
     /// Connects to a remote ALPINE device using the provided credentials.
+    ///
+    /// `endpoints` accepts anything convertible into [`Endpoints`] — a single
+    /// `SocketAddr`, a `Vec<SocketAddr>`, or an [`Endpoints::Resolver`] — and each
+    /// candidate is tried in order, falling through to the next on handshake
+    /// timeout or transport error, until one fully establishes. The resulting
+    /// client supervises its own connection: a keepalive loss triggers an
+    /// automatic reconnect with exponential backoff, observable through
+    /// [`AlpineClient::connection_state`].
     pub async fn connect(
         local_addr: SocketAddr,
-        remote_addr: SocketAddr,
+        endpoints: impl Into<Endpoints>,
         identity: DeviceIdentity,
         capabilities: CapabilitySet,
         credentials: NodeCredentials,
     ) -> Result<Self, ClientError> {
-        let key_exchange = X25519KeyExchange::new();
-        let authenticator = crate::session::Ed25519Authenticator::new(credentials.clone());
+        Self::connect_with_resume(local_addr, endpoints, identity, capabilities, credentials, None).await
+    }
+
+    /// Like [`AlpineClient::connect`], but attempts `AlnpSession::resume` with
+    /// `resume_token` against each candidate before falling back to a full
+    /// handshake. Used by [`AlpineClient::connect_cached`] to carry a
+    /// previously cached resumption token into the initial connect attempt.
+    async fn connect_with_resume(
+        local_addr: SocketAddr,
+        endpoints: impl Into<Endpoints>,
+        identity: DeviceIdentity,
+        capabilities: CapabilitySet,
+        credentials: NodeCredentials,
+        resume_token: Option<Vec<u8>>,
+    ) -> Result<Self, ClientError> {
+        let endpoints: Endpoints = endpoints.into();
+        let candidates = endpoints.resolve().await;
+        let (loss_tx, loss_rx) = mpsc::unbounded_channel();
+
+        let attempt = try_candidates(&candidates, |remote_addr| {
+            Self::connect_one(
+                local_addr,
+                remote_addr,
+                identity.clone(),
+                capabilities.clone(),
+                credentials.clone(),
+                resume_token.clone(),
+                loss_tx.clone(),
+            )
+        })
+        .await;
+
+        let (remote_addr, inner) = match attempt {
+            Ok(attempt) => attempt,
+            Err(attempts) => return Err(ClientError::AllEndpointsFailed(attempts)),
+        };
 
-        let mut transport =
-            TimeoutTransport::new(CborUdpTransport::bind(local_addr, remote_addr, 2048).await?, Duration::from_secs(3));
-        let session = AlnpSession::connect(
+        let seq_counter = Arc::new(AtomicU64::new(0));
+        let (state_tx, state_rx) = watch::channel(ConnectionState::Connected);
+        let inner = Arc::new(Mutex::new(inner));
+        let params = ReconnectParams {
+            local_addr,
+            endpoints,
+            preferred: remote_addr,
             identity,
-            capabilities.clone(),
-            authenticator,
-            key_exchange,
-            HandshakeContext::default(),
-            &mut transport,
-        )
-        .await?;
+            capabilities,
+            credentials,
+            loss_tx,
+        };
+        let supervisor_handle =
+            tokio::spawn(Self::supervise(inner.clone(), loss_rx, state_tx.clone(), params));
+
+        Ok(Self {
+            inner,
+            seq_counter,
+            state_tx,
+            state_rx,
+            supervisor_handle: Some(supervisor_handle),
+        })
+    }
+
+    /// Runs the full handshake/session/stream setup against a single resolved endpoint.
+    ///
+    /// If `resume_token` is `Some`, a fast `AlnpSession::resume` is attempted
+    /// first; a peer that has already dropped the referenced session (or
+    /// doesn't support resumption) rejects it, and this falls back to a full
+    /// `X25519KeyExchange` handshake rather than failing the connect attempt.
+    async fn connect_one(
+        local_addr: SocketAddr,
+        remote_addr: SocketAddr,
+        identity: DeviceIdentity,
+        capabilities: CapabilitySet,
+        credentials: NodeCredentials,
+        resume_token: Option<Vec<u8>>,
+        loss_tx: mpsc::UnboundedSender<()>,
+    ) -> Result<ClientInner, ClientError> {
+        // One bound socket carries handshake/control, keepalive, and stream frames;
+        // the mux's reader task tags each inbound datagram and routes it accordingly.
+        let (mux, handshake_rx, stream_rx) = SocketMux::bind(local_addr, remote_addr).await?;
+        let socket = mux.socket();
+
+        let mut transport = TimeoutTransport::new(
+            MuxedHandshakeTransport::new(socket.clone(), handshake_rx),
+            Duration::from_secs(3),
+        );
+
+        let resumed = match resume_token {
+            Some(token) => {
+                let key_exchange = X25519KeyExchange::new();
+                let authenticator = crate::session::Ed25519Authenticator::new(credentials.clone());
+                AlnpSession::resume(
+                    identity.clone(),
+                    capabilities.clone(),
+                    authenticator,
+                    key_exchange,
+                    HandshakeContext::default(),
+                    token,
+                    &mut transport,
+                )
+                .await
+                .ok()
+            }
+            None => None,
+        };
+        let session = match resumed {
+            Some(session) => session,
+            None => {
+                let key_exchange = X25519KeyExchange::new();
+                let authenticator = crate::session::Ed25519Authenticator::new(credentials.clone());
+                AlnpSession::connect(
+                    identity,
+                    capabilities.clone(),
+                    authenticator,
+                    key_exchange,
+                    HandshakeContext::default(),
+                    &mut transport,
+                )
+                .await?
+            }
+        };
 
         let transport = Arc::new(Mutex::new(transport));
         let keepalive_handle = tokio::spawn(keepalive::spawn_keepalive(
@@ -121,10 +311,12 @@ impl AlpineClient {
                 .established()
                 .ok_or_else(|| ClientError::Io("session missing after handshake".into()))?
                 .session_id,
+            loss_tx,
         ));
 
-        let stream_socket = UdpFrameTransport::new(local_addr, remote_addr)?;
-        let stream = AlnpStream::new(session.clone(), stream_socket);
+        let frame_transport = MuxedFrameTransport::new(socket);
+        let stream = AlnpStream::new(session.clone(), frame_transport.clone());
+        let channels = ChannelManager::new(frame_transport, stream_rx);
 
         let established = session
             .established()
@@ -137,18 +329,93 @@ impl AlpineClient {
                 .ok_or_else(|| ClientError::Io("session keys missing".into()))?,
         );
         let control = ControlClient::new(device_uuid, established.session_id, control_crypto);
+        let resumption_token = established.resumption_token.clone();
 
-        Ok(Self {
+        Ok(ClientInner {
             session,
             transport,
             stream,
             control,
-            keepalive_handle: Some(keepalive_handle),
+            channels,
+            keepalive_handle,
+            remote_addr,
+            resumption_token,
+            _mux: mux,
         })
     }
 
+    /// Watches for keepalive loss and drives reconnection until the client is closed.
+    async fn supervise(
+        inner: Arc<Mutex<ClientInner>>,
+        mut loss_rx: mpsc::UnboundedReceiver<()>,
+        state_tx: watch::Sender<ConnectionState>,
+        mut params: ReconnectParams,
+    ) {
+        let mut backoff = Backoff::new(Duration::from_millis(250), Duration::from_secs(30));
+
+        while loss_rx.recv().await.is_some() {
+            if *state_tx.borrow() == ConnectionState::Closed {
+                return;
+            }
+            let _ = state_tx.send(ConnectionState::Reconnecting);
+
+            loop {
+                if *state_tx.borrow() == ConnectionState::Closed {
+                    return;
+                }
+
+                // Re-resolve every attempt round rather than reusing the
+                // candidate list from the last successful connect: an
+                // `Endpoints::Resolver` whose answer has since changed (DNS
+                // failover, a multi-homed controller moving networks) would
+                // otherwise retry addresses that can never work again.
+                let candidates = reorder_preferring(&params.endpoints.resolve().await, params.preferred);
+                let resume_token = inner.lock().await.resumption_token.clone();
+
+                let attempt = try_candidates(&candidates, |remote_addr| {
+                    Self::connect_one(
+                        params.local_addr,
+                        remote_addr,
+                        params.identity.clone(),
+                        params.capabilities.clone(),
+                        params.credentials.clone(),
+                        resume_token.clone(),
+                        params.loss_tx.clone(),
+                    )
+                })
+                .await;
+
+                match attempt {
+                    Ok((remote_addr, new_inner)) => {
+                        let mut guard = inner.lock().await;
+                        guard.keepalive_handle.abort();
+                        *guard = new_inner;
+                        drop(guard);
+                        params.preferred = remote_addr;
+                        backoff.reset();
+                        let _ = state_tx.send(ConnectionState::Connected);
+                        break;
+                    }
+                    Err(_attempted) => {
+                        tokio::time::sleep(backoff.next_delay()).await;
+                    }
+                }
+            }
+        }
+    }
+
+    /// The endpoint that actually established the current session.
+    pub async fn remote_addr(&self) -> SocketAddr {
+        self.inner.lock().await.remote_addr
+    }
+
+    /// Observes the client's connection lifecycle (connected, reconnecting, closed).
+    pub fn connection_state(&self) -> watch::Receiver<ConnectionState> {
+        self.state_rx.clone()
+    }
+
     /// Sends a streaming frame via the high-level helper.
-    pub fn send_frame(
+    pub async fn send_frame(
         &self,
         channel_format: ChannelFormat,
         channels: Vec<u16>,
@@ -156,26 +423,222 @@ impl AlpineClient {
         groups: Option<HashMap<String, Vec<u16>>>,
         metadata: Option<HashMap<String, serde_json::Value>>,
     ) -> Result<(), ClientError> {
-        self.stream
+        self.inner
+            .lock()
+            .await
+            .stream
             .send(channel_format, channels, priority, groups, metadata)
             .map_err(ClientError::from)
     }
 
-    /// Gracefully closes the client, stopping keepalive tasks.
-    pub async fn close(mut self) {
-        self.session.close();
-        if let Some(handle) = self.keepalive_handle.take() {
+    /// Opens a new logical channel over the current session, sharing this
+    /// client's socket, session keys, and keepalive with any other open
+    /// channels. See [`ChannelManager`] for how frames are tagged and routed.
+    pub async fn open_channel(&self, format: ChannelFormat, priority: u8) -> ChannelHandle {
+        let channels = self.inner.lock().await.channels.clone();
+        channels.open_channel(self.inner.clone(), format, priority).await
+    }
+
+    /// Gracefully closes the client, stopping keepalive and reconnect supervision.
+    ///
+    /// Sends [`ConnectionState::Closed`] before tearing anything down, so any
+    /// reconnect attempt already in flight sees it on its next check and bails
+    /// out instead of racing a fresh session into existence after `close`
+    /// returns.
+    pub async fn close(self) {
+        let _ = self.state_tx.send(ConnectionState::Closed);
+        if let Some(handle) = &self.supervisor_handle {
             handle.abort();
         }
+        let mut guard = self.inner.lock().await;
+        guard.session.close();
+        guard.keepalive_handle.abort();
     }
 
     /// Builds an authenticated control envelope ready for transport.
-    pub fn control_envelope(
-        &self,
-        seq: u64,
-        op: ControlOp,
-        payload: Value,
-    ) -> Result<ControlEnvelope, HandshakeError> {
-        self.control.envelope(seq, op, payload)
+    ///
+    /// The sequence number is drawn from a counter owned by the client rather
+    /// than the caller, so it stays monotonic across reconnects even though the
+    /// underlying `ControlClient` is rebuilt each time.
+    pub async fn control_envelope(&self, op: ControlOp, payload: Value) -> Result<ControlEnvelope, HandshakeError> {
+        let seq = self.seq_counter.fetch_add(1, Ordering::SeqCst);
+        self.inner.lock().await.control.envelope(seq, op, payload)
+    }
+}
+
+#[cfg(feature = "persistent-cache")]
+impl AlpineClient {
+    /// Like [`AlpineClient::connect`], but consults `cache` first.
+    ///
+    /// On a cache hit for `identity`, the cached `NodeCredentials` and
+    /// `CapabilitySet` are reused (skipping identity key regeneration) and the
+    /// cached endpoint is moved to the front of the resolved `endpoints` when
+    /// they include it; a cached endpoint they no longer include is not tried.
+    /// `endpoints` is resolved once here, so reconnects of the returned client
+    /// retry that resolved list and do not invoke a resolver again. A cached
+    /// resumption token is carried along so the peer can fast-resume the prior
+    /// session where it supports doing so; if it doesn't, the handshake simply
+    /// falls back to a full `X25519KeyExchange` exchange. Either way, once the
+    /// session establishes, the refreshed credentials, capabilities, endpoint,
+    /// and resumption token are written back to `cache`.
+    pub async fn connect_cached(
+        local_addr: SocketAddr,
+        endpoints: impl Into<Endpoints>,
+        identity: DeviceIdentity,
+        capabilities: CapabilitySet,
+        credentials: NodeCredentials,
+        cache: Arc<dyn super::cache::Cache>,
+    ) -> Result<Self, ClientError> {
+        // `Cache` is a plain synchronous trait (so no-std/embedded implementors
+        // aren't forced to pull in an async runtime), but `FileCache`'s default
+        // impl does real blocking file I/O; running it straight from this async
+        // body would stall the worker thread it's polled on. Push it onto a
+        // blocking-pool thread instead.
+        let cache_for_load = cache.clone();
+        let identity_for_load = identity.clone();
+        let cached = tokio::task::spawn_blocking(move || cache_for_load.load(&identity_for_load))
+            .await
+            .unwrap_or(None);
+        let (capabilities, credentials) = match &cached {
+            Some(hit) => (hit.capabilities.clone(), hit.credentials.clone()),
+            None => (capabilities, credentials),
+        };
+
+        let mut candidates = endpoints.into().resolve().await;
+        if let Some(hit) = &cached {
+            candidates = reorder_preferring(&candidates, hit.last_endpoint);
+        }
+
+        let resume_token = cached.as_ref().and_then(|hit| hit.resumption_token.clone());
+        let client = Self::connect_with_resume(
+            local_addr,
+            candidates,
+            identity.clone(),
+            capabilities.clone(),
+            credentials.clone(),
+            resume_token,
+        )
+        .await?;
+
+        let resumption_token = client.inner.lock().await.resumption_token.clone();
+        let session = super::cache::CachedSession {
+            credentials,
+            capabilities,
+            last_endpoint: client.remote_addr().await,
+            resumption_token,
+        };
+        let _ = tokio::task::spawn_blocking(move || cache.store(&identity, &session)).await;
+
+        Ok(client)
+    }
+}
+
+/// Moves `preferred` to the front of `candidates` (deduplicated), so a
+/// reconnect tries the endpoint that last worked before falling back to the
+/// original candidate order.
+///
+/// Leaves `candidates` untouched if `preferred` isn't among them: a resolver
+/// whose answer has since changed may no longer return it at all, and
+/// inserting it anyway would waste a full connect attempt retrying an
+/// address that, per the caller's own re-resolve, can never work again.
+fn reorder_preferring(candidates: &[SocketAddr], preferred: SocketAddr) -> Vec<SocketAddr> {
+    if !candidates.contains(&preferred) {
+        return candidates.to_vec();
+    }
+    let mut ordered = Vec::with_capacity(candidates.len());
+    ordered.push(preferred);
+    ordered.extend(candidates.iter().copied().filter(|addr| *addr != preferred));
+    ordered
+}
+
+/// Tries `attempt` against each of `candidates` in order, returning the first
+/// endpoint to succeed (alongside its value) or every endpoint's error if
+/// none did. Shared by the initial `connect` and the reconnect loop in
+/// `supervise`, which both just want "first candidate to fully establish wins."
+async fn try_candidates<T, F, Fut>(
+    candidates: &[SocketAddr],
+    mut attempt: F,
+) -> Result<(SocketAddr, T), Vec<(SocketAddr, String)>>
+where
+    F: FnMut(SocketAddr) -> Fut,
+    Fut: Future<Output = Result<T, ClientError>>,
+{
+    let mut attempts = Vec::new();
+    for &candidate in candidates {
+        match attempt(candidate).await {
+            Ok(value) => return Ok((candidate, value)),
+            Err(err) => attempts.push((candidate, err.to_string())),
+        }
+    }
+    Err(attempts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{reorder_preferring, try_candidates, ClientError};
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn addr(port: u16) -> SocketAddr {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port)
+    }
+
+    #[test]
+    fn reorder_preferring_moves_preferred_to_front_and_dedupes() {
+        let candidates = vec![addr(1), addr(2), addr(3)];
+        assert_eq!(reorder_preferring(&candidates, addr(2)), vec![addr(2), addr(1), addr(3)]);
+        // The preferred endpoint may already be a duplicate of itself in the
+        // source list (e.g. a resolver returning it twice); it must still
+        // appear exactly once in the result.
+        let with_dup = vec![addr(2), addr(1), addr(2)];
+        assert_eq!(reorder_preferring(&with_dup, addr(2)), vec![addr(2), addr(1)]);
+    }
+
+    #[test]
+    fn reorder_preferring_is_a_no_op_when_preferred_is_already_first() {
+        let candidates = vec![addr(1), addr(2)];
+        assert_eq!(reorder_preferring(&candidates, addr(1)), vec![addr(1), addr(2)]);
+    }
+
+    #[test]
+    fn reorder_preferring_leaves_candidates_untouched_when_preferred_is_absent() {
+        let candidates = vec![addr(1), addr(2)];
+        assert_eq!(reorder_preferring(&candidates, addr(99)), vec![addr(1), addr(2)]);
+    }
+
+    #[tokio::test]
+    async fn try_candidates_returns_first_success_without_trying_the_rest() {
+        let candidates = vec![addr(1), addr(2), addr(3)];
+        let tried = AtomicUsize::new(0);
+
+        let result = try_candidates(&candidates, |candidate| {
+            tried.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if candidate == addr(2) {
+                    Ok("connected")
+                } else {
+                    Err(ClientError::Io("refused".into()))
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result, Ok((addr(2), "connected")));
+        assert_eq!(tried.load(Ordering::SeqCst), 2, "should stop at the first success");
+    }
+
+    #[tokio::test]
+    async fn try_candidates_collects_every_error_when_all_fail() {
+        let candidates = vec![addr(1), addr(2)];
+
+        let result = try_candidates(&candidates, |candidate| async move {
+            Err::<(), _>(ClientError::Io(format!("refused {}", candidate)))
+        })
+        .await;
+
+        let attempts = result.expect_err("all candidates failed");
+        assert_eq!(attempts.len(), 2);
+        assert_eq!(attempts[0].0, addr(1));
+        assert_eq!(attempts[1].0, addr(2));
     }
 }
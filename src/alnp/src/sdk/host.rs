@@ -0,0 +1,382 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::net::UdpSocket;
+use tokio::sync::{mpsc, Mutex};
+
+use crate::crypto::identity::NodeCredentials;
+use crate::crypto::X25519KeyExchange;
+use crate::handshake::transport::HandshakeTransport;
+use crate::handshake::{ChallengeAuthenticator, HandshakeContext, HandshakeError};
+use crate::messages::{CapabilitySet, DeviceIdentity};
+use crate::session::AlnpSession;
+
+use super::client::ClientError;
+use super::slab::Slab;
+
+/// Stable identifier for a slot in either the handshake or session arena.
+pub type Token = usize;
+
+/// Cap on concurrently in-progress handshakes, independent of `MAX_SESSIONS` so a
+/// flood of half-open peers can never starve already-authenticated controllers.
+pub const MAX_HANDSHAKES: usize = 256;
+
+/// Cap on concurrently established sessions.
+pub const MAX_SESSIONS: usize = 1024;
+
+/// How long a session may go without a keepalive before [`AlpineHost::reap_stale_sessions`] evicts it.
+const SESSION_STALE_AFTER: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Copy)]
+enum Route {
+    Handshake(Token),
+    Session(Token),
+}
+
+/// A handshake in flight: the task driving it is fed inbound datagrams over `inbound`.
+struct PendingHandshake {
+    addr: SocketAddr,
+    inbound: mpsc::UnboundedSender<Vec<u8>>,
+}
+
+struct EstablishedSession {
+    session: AlnpSession,
+    addr: SocketAddr,
+    last_keepalive: Instant,
+    /// Forwards raw inbound datagrams for this session so a caller that has
+    /// taken the session via [`AlpineHost::session`] can also drain its traffic.
+    datagrams_tx: mpsc::UnboundedSender<Vec<u8>>,
+    /// Taken at most once, by [`AlpineHost::take_session_datagrams`].
+    datagrams_rx: Option<mpsc::UnboundedReceiver<Vec<u8>>>,
+}
+
+/// Observable host-level events, surfaced so a caller can learn when a
+/// controller is accepted (and go fetch its session/datagrams), when a
+/// handshake is rejected outright for want of capacity, or when a completed
+/// handshake's session is rejected because the session slab filled up first.
+pub enum HostEvent {
+    SessionEstablished { token: Token, addr: SocketAddr },
+    HandshakeRejected { addr: SocketAddr },
+    SessionRejected { addr: SocketAddr },
+}
+
+/// Result of a handshake task, reported back to the accept loop so it can move
+/// the peer from the handshake slab into the session slab.
+enum Outcome {
+    Established {
+        handshake_token: Token,
+        addr: SocketAddr,
+        session: AlnpSession,
+    },
+    Failed {
+        handshake_token: Token,
+        addr: SocketAddr,
+    },
+}
+
+/// Per-peer [`HandshakeTransport`] backed by the host's shared socket. Datagrams
+/// addressed to `peer` are handed to it over `inbound` by the accept loop; replies
+/// go straight out the shared socket.
+struct PeerHandshakeTransport {
+    socket: Arc<UdpSocket>,
+    peer: SocketAddr,
+    inbound: mpsc::UnboundedReceiver<Vec<u8>>,
+}
+
+impl HandshakeTransport for PeerHandshakeTransport {
+    async fn send(&mut self, bytes: &[u8]) -> Result<(), HandshakeError> {
+        self.socket
+            .send_to(bytes, self.peer)
+            .await
+            .map_err(|err| HandshakeError::Transport(err.to_string()))?;
+        Ok(())
+    }
+
+    async fn recv(&mut self) -> Result<Vec<u8>, HandshakeError> {
+        self.inbound
+            .recv()
+            .await
+            .ok_or_else(|| HandshakeError::Transport("peer disconnected".into()))
+    }
+}
+
+/// Server-side counterpart to [`AlpineClient`](super::client::AlpineClient): accepts
+/// and multiplexes many concurrent controllers over a single bound `UdpSocket`.
+///
+/// In-progress handshakes and fully established sessions live in separate slab
+/// arenas indexed by `Token`, each with its own capacity cap. A
+/// `HashMap<SocketAddr, Route>` routes each inbound datagram to the right slot;
+/// when a handshake completes, its slot is atomically replaced by a session slot
+/// under a fresh token, and the route is rewritten to point at it.
+pub struct AlpineHost {
+    socket: Arc<UdpSocket>,
+    identity: DeviceIdentity,
+    capabilities: CapabilitySet,
+    credentials: NodeCredentials,
+    routes: Mutex<HashMap<SocketAddr, Route>>,
+    handshakes: Mutex<Slab<PendingHandshake>>,
+    sessions: Mutex<Slab<EstablishedSession>>,
+    outcomes_tx: mpsc::UnboundedSender<Outcome>,
+    outcomes_rx: Mutex<mpsc::UnboundedReceiver<Outcome>>,
+    events_tx: mpsc::UnboundedSender<HostEvent>,
+}
+
+impl AlpineHost {
+    /// Binds the host to `local_addr`, ready to accept controllers that authenticate
+    /// with a credential recognized by `credentials`. Returns the host alongside a
+    /// receiver of [`HostEvent`]s: watch it to learn when a session establishes
+    /// (then fetch it with [`AlpineHost::session`]) or when a handshake is
+    /// rejected because the handshake pool is saturated.
+    pub async fn bind(
+        local_addr: SocketAddr,
+        identity: DeviceIdentity,
+        capabilities: CapabilitySet,
+        credentials: NodeCredentials,
+    ) -> Result<(Self, mpsc::UnboundedReceiver<HostEvent>), ClientError> {
+        let socket = Arc::new(UdpSocket::bind(local_addr).await?);
+        let (outcomes_tx, outcomes_rx) = mpsc::unbounded_channel();
+        let (events_tx, events_rx) = mpsc::unbounded_channel();
+        Ok((
+            Self {
+                socket,
+                identity,
+                capabilities,
+                credentials,
+                routes: Mutex::new(HashMap::new()),
+                handshakes: Mutex::new(Slab::with_capacity(MAX_HANDSHAKES)),
+                sessions: Mutex::new(Slab::with_capacity(MAX_SESSIONS)),
+                outcomes_tx,
+                outcomes_rx: Mutex::new(outcomes_rx),
+                events_tx,
+            },
+            events_rx,
+        ))
+    }
+
+    /// Runs the accept loop: reads inbound datagrams and routes them to the
+    /// matching handshake or session slot until the socket errors out.
+    pub async fn run(&self) -> Result<(), ClientError> {
+        let mut buf = vec![0u8; 2048];
+        loop {
+            let mut outcomes = self.outcomes_rx.lock().await;
+            tokio::select! {
+                received = self.socket.recv_from(&mut buf) => {
+                    drop(outcomes);
+                    let (len, addr) = received?;
+                    self.on_datagram(addr, &buf[..len]).await;
+                }
+                Some(outcome) = outcomes.recv() => {
+                    drop(outcomes);
+                    self.apply_outcome(outcome).await;
+                }
+            }
+        }
+    }
+
+    async fn on_datagram(&self, addr: SocketAddr, bytes: &[u8]) {
+        let route = self.routes.lock().await.get(&addr).copied();
+        match route {
+            Some(Route::Session(token)) => self.on_session_datagram(token, bytes).await,
+            Some(Route::Handshake(token)) => {
+                let handshakes = self.handshakes.lock().await;
+                if let Some(pending) = handshakes.get(token) {
+                    // Drop the payload quietly if the handshake task has already
+                    // exited; its `Outcome` is already on the way.
+                    let _ = pending.inbound.send(bytes.to_vec());
+                }
+            }
+            None => self.start_handshake(addr, bytes).await,
+        }
+    }
+
+    async fn start_handshake(&self, addr: SocketAddr, bytes: &[u8]) {
+        let mut handshakes = self.handshakes.lock().await;
+        if handshakes.is_full() {
+            // Reject outright: a flood of half-open peers must never starve the
+            // session slab. No handshake reply is owed to an unauthenticated
+            // peer, but the rejection is surfaced to the caller as a `HostEvent`
+            // so it's observable (logging, metrics, alerting).
+            let _ = self.events_tx.send(HostEvent::HandshakeRejected { addr });
+            return;
+        }
+        let (inbound_tx, inbound_rx) = mpsc::unbounded_channel();
+        let token = handshakes
+            .insert(PendingHandshake { addr, inbound: inbound_tx.clone() })
+            .expect("checked is_full above");
+        drop(handshakes);
+
+        self.routes.lock().await.insert(addr, Route::Handshake(token));
+        let _ = inbound_tx.send(bytes.to_vec());
+
+        let socket = self.socket.clone();
+        let identity = self.identity.clone();
+        let capabilities = self.capabilities.clone();
+        let credentials = self.credentials.clone();
+        let outcomes_tx = self.outcomes_tx.clone();
+
+        tokio::spawn(async move {
+            let authenticator = ChallengeAuthenticator::new(credentials);
+            let key_exchange = X25519KeyExchange::new();
+            let mut transport = PeerHandshakeTransport { socket, peer: addr, inbound: inbound_rx };
+
+            let result = AlnpSession::accept(
+                identity,
+                capabilities,
+                authenticator,
+                key_exchange,
+                HandshakeContext::default(),
+                &mut transport,
+            )
+            .await;
+
+            let outcome = match result {
+                Ok(session) => Outcome::Established { handshake_token: token, addr, session },
+                Err(_) => Outcome::Failed { handshake_token: token, addr },
+            };
+            let _ = outcomes_tx.send(outcome);
+        });
+    }
+
+    async fn apply_outcome(&self, outcome: Outcome) {
+        match outcome {
+            Outcome::Established { handshake_token, addr, session } => {
+                self.handshakes.lock().await.remove(handshake_token);
+
+                let mut sessions = self.sessions.lock().await;
+                if sessions.is_full() {
+                    self.routes.lock().await.remove(&addr);
+                    // The peer completed a full handshake only to find no room
+                    // for it, mirroring the saturated-handshake-pool case. Surface
+                    // it the same way so a caller can e.g. log it or otherwise
+                    // avoid a peer looping forever on a doomed reconnect attempt.
+                    let _ = self.events_tx.send(HostEvent::SessionRejected { addr });
+                    return;
+                }
+                let (datagrams_tx, datagrams_rx) = mpsc::unbounded_channel();
+                let token = sessions
+                    .insert(EstablishedSession {
+                        session,
+                        addr,
+                        last_keepalive: Instant::now(),
+                        datagrams_tx,
+                        datagrams_rx: Some(datagrams_rx),
+                    })
+                    .expect("checked is_full above");
+                drop(sessions);
+
+                // Rewrite the route so the peer's stable identity becomes its
+                // session token rather than its now-defunct handshake token.
+                self.routes.lock().await.insert(addr, Route::Session(token));
+                let _ = self.events_tx.send(HostEvent::SessionEstablished { token, addr });
+            }
+            Outcome::Failed { handshake_token, addr } => {
+                self.handshakes.lock().await.remove(handshake_token);
+                self.routes.lock().await.remove(&addr);
+            }
+        }
+    }
+
+    async fn on_session_datagram(&self, token: Token, bytes: &[u8]) {
+        if let Some(entry) = self.sessions.lock().await.get_mut(token) {
+            entry.last_keepalive = Instant::now();
+            let _ = entry.datagrams_tx.send(bytes.to_vec());
+        }
+    }
+
+    /// Looks up the [`AlnpSession`] for an established controller, if `token`
+    /// still refers to one. Clone it to drive control/stream traffic; the host
+    /// keeps forwarding raw datagrams to it regardless of how many clones exist.
+    pub async fn session(&self, token: Token) -> Option<AlnpSession> {
+        self.sessions.lock().await.get(token).map(|entry| entry.session.clone())
+    }
+
+    /// Takes the raw inbound datagram stream for an established session, so a
+    /// caller that has fetched the session via [`AlpineHost::session`] can also
+    /// drive its control/stream transport. Returns `None` if `token` doesn't
+    /// refer to a live session or the datagrams were already taken.
+    pub async fn take_session_datagrams(&self, token: Token) -> Option<mpsc::UnboundedReceiver<Vec<u8>>> {
+        self.sessions.lock().await.get_mut(token)?.datagrams_rx.take()
+    }
+
+    /// Evicts sessions whose keepalive has gone stale for longer than 30 seconds,
+    /// freeing their slot and route for reuse.
+    pub async fn reap_stale_sessions(&self) {
+        let mut sessions = self.sessions.lock().await;
+        let ages = sessions
+            .iter()
+            .map(|(token, entry)| (token, entry.addr, entry.last_keepalive.elapsed()));
+        let stale = stale_entries(ages, SESSION_STALE_AFTER);
+        for (token, _) in &stale {
+            sessions.remove(*token);
+        }
+        drop(sessions);
+
+        if !stale.is_empty() {
+            let mut routes = self.routes.lock().await;
+            for (_, addr) in stale {
+                routes.remove(&addr);
+            }
+        }
+    }
+
+    /// Number of fully established sessions currently held.
+    pub async fn session_count(&self) -> usize {
+        self.sessions.lock().await.len()
+    }
+
+    /// Number of handshakes currently in flight.
+    pub async fn pending_handshake_count(&self) -> usize {
+        self.handshakes.lock().await.len()
+    }
+}
+
+/// Pure selection logic for [`AlpineHost::reap_stale_sessions`]: given each
+/// session's current idle duration, returns the ones that have gone stale for
+/// longer than `after`. Split out from the locking/removal around it so it's
+/// unit-testable without a live `AlnpSession`, which otherwise makes this
+/// method awkward to exercise in isolation.
+fn stale_entries(
+    ages: impl Iterator<Item = (Token, SocketAddr, Duration)>,
+    after: Duration,
+) -> Vec<(Token, SocketAddr)> {
+    ages.filter(|(_, _, age)| *age > after)
+        .map(|(token, addr, _)| (token, addr))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::stale_entries;
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use std::time::Duration;
+
+    fn addr(port: u16) -> SocketAddr {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port)
+    }
+
+    #[test]
+    fn selects_only_sessions_past_the_threshold() {
+        let ages = vec![
+            (1, addr(1), Duration::from_secs(10)),
+            (2, addr(2), Duration::from_secs(45)),
+        ];
+        assert_eq!(
+            stale_entries(ages.into_iter(), Duration::from_secs(30)),
+            vec![(2, addr(2))]
+        );
+    }
+
+    #[test]
+    fn a_session_exactly_at_the_threshold_is_not_yet_stale() {
+        let ages = vec![(1, addr(1), Duration::from_secs(30))];
+        assert!(stale_entries(ages.into_iter(), Duration::from_secs(30)).is_empty());
+    }
+
+    #[test]
+    fn empty_input_yields_no_stale_sessions() {
+        let ages: Vec<(super::Token, SocketAddr, Duration)> = Vec::new();
+        assert!(stale_entries(ages.into_iter(), Duration::from_secs(30)).is_empty());
+    }
+}
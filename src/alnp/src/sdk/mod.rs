@@ -0,0 +1,17 @@
+//! High-level SDK built on top of the handshake, session, control, and stream layers.
+
+#[cfg(feature = "persistent-cache")]
+mod cache;
+mod channel;
+pub mod client;
+pub mod host;
+mod mux;
+mod reconnect;
+mod slab;
+
+#[cfg(feature = "persistent-cache")]
+pub use cache::{Cache, CachedSession, FileCache};
+pub use channel::{ChannelHandle, ChannelId, ChannelManager};
+pub use client::{AlpineClient, ClientError, Endpoints};
+pub use host::{AlpineHost, HostEvent};
+pub use reconnect::ConnectionState;
@@ -0,0 +1,132 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::handshake::transport::HandshakeTransport;
+use crate::handshake::HandshakeError;
+use crate::messages::MessageType;
+use crate::stream::FrameTransport;
+
+const DATAGRAM_BUF: usize = 2048;
+
+/// One bound UDP socket shared by the handshake/control path and the streaming
+/// path, plus the reader task that demultiplexes inbound datagrams between them.
+///
+/// Binding a single socket (instead of one per path) avoids the double-bind
+/// fragility of having two sockets on the same `local_addr`, and it fixes NAT
+/// traversal: replies always arrive on the port the peer already learned.
+pub(crate) struct SocketMux {
+    socket: Arc<UdpSocket>,
+    reader: JoinHandle<()>,
+}
+
+impl SocketMux {
+    /// Binds `local`, connects to `peer`, and spawns the demultiplexing reader.
+    /// Returns the mux plus the two channels it feeds: handshake/control CBOR
+    /// envelopes, and raw streaming frames.
+    pub(crate) async fn bind(
+        local: SocketAddr,
+        peer: SocketAddr,
+    ) -> Result<(Self, mpsc::UnboundedReceiver<Vec<u8>>, mpsc::UnboundedReceiver<Vec<u8>>), std::io::Error> {
+        let socket = Arc::new(UdpSocket::bind(local).await?);
+        socket.connect(peer).await?;
+
+        let (handshake_tx, handshake_rx) = mpsc::unbounded_channel();
+        let (stream_tx, stream_rx) = mpsc::unbounded_channel();
+
+        let reader_socket = socket.clone();
+        let reader = tokio::spawn(async move {
+            let mut buf = vec![0u8; DATAGRAM_BUF];
+            loop {
+                let len = match reader_socket.recv(&mut buf).await {
+                    Ok(len) => len,
+                    Err(_) => return,
+                };
+                let datagram = buf[..len].to_vec();
+                let sent = if is_stream_frame(&datagram) {
+                    stream_tx.send(datagram)
+                } else {
+                    handshake_tx.send(datagram)
+                };
+                if sent.is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok((Self { socket, reader }, handshake_rx, stream_rx))
+    }
+
+    pub(crate) fn socket(&self) -> Arc<UdpSocket> {
+        self.socket.clone()
+    }
+}
+
+impl Drop for SocketMux {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+/// Reads the leading `MessageType` tag to decide whether a datagram belongs to
+/// the streaming path rather than the handshake/control CBOR path.
+fn is_stream_frame(datagram: &[u8]) -> bool {
+    matches!(datagram.first(), Some(tag) if *tag == MessageType::StreamFrame as u8)
+}
+
+/// [`HandshakeTransport`] backed by the shared socket's handshake/control channel.
+pub(crate) struct MuxedHandshakeTransport {
+    socket: Arc<UdpSocket>,
+    inbound: mpsc::UnboundedReceiver<Vec<u8>>,
+}
+
+impl MuxedHandshakeTransport {
+    pub(crate) fn new(socket: Arc<UdpSocket>, inbound: mpsc::UnboundedReceiver<Vec<u8>>) -> Self {
+        Self { socket, inbound }
+    }
+}
+
+impl HandshakeTransport for MuxedHandshakeTransport {
+    async fn send(&mut self, bytes: &[u8]) -> Result<(), HandshakeError> {
+        self.socket
+            .send(bytes)
+            .await
+            .map_err(|err| HandshakeError::Transport(err.to_string()))?;
+        Ok(())
+    }
+
+    async fn recv(&mut self) -> Result<Vec<u8>, HandshakeError> {
+        self.inbound
+            .recv()
+            .await
+            .ok_or_else(|| HandshakeError::Transport("socket closed".into()))
+    }
+}
+
+/// [`FrameTransport`] for the streaming path, backed by the same shared socket.
+/// Cheap to construct more than once from the same socket handle: the stream
+/// layer and a [`ChannelManager`](super::channel::ChannelManager) each get their
+/// own instance, while inbound stream frames are fanned out separately (see
+/// [`SocketMux::bind`]'s `stream_rx`) for the channel dispatcher to drain.
+#[derive(Clone)]
+pub(crate) struct MuxedFrameTransport {
+    socket: Arc<UdpSocket>,
+}
+
+impl MuxedFrameTransport {
+    pub(crate) fn new(socket: Arc<UdpSocket>) -> Self {
+        Self { socket }
+    }
+}
+
+impl FrameTransport for MuxedFrameTransport {
+    fn send_frame(&self, bytes: &[u8]) -> Result<(), String> {
+        self.socket
+            .try_send(bytes)
+            .map_err(|e| format!("udp stream send: {}", e))?;
+        Ok(())
+    }
+}
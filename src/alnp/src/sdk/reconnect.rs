@@ -0,0 +1,92 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Observable connection lifecycle for a reconnecting [`AlpineClient`](super::client::AlpineClient).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connected,
+    Reconnecting,
+    Closed,
+}
+
+/// Exponential backoff with light jitter, capped at a configurable ceiling.
+pub(crate) struct Backoff {
+    base: Duration,
+    cap: Duration,
+    attempt: u32,
+}
+
+impl Backoff {
+    pub(crate) fn new(base: Duration, cap: Duration) -> Self {
+        Self { base, cap, attempt: 0 }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.attempt = 0;
+    }
+
+    /// Delay for the next attempt. Doubles each call up to `cap`, then advances the counter.
+    pub(crate) fn next_delay(&mut self) -> Duration {
+        let shift = self.attempt.min(16);
+        let exp = self.base.saturating_mul(1u32 << shift);
+        let capped = exp.min(self.cap);
+        self.attempt += 1;
+        jittered(capped)
+    }
+}
+
+/// Spreads `delay` by up to +/-20% so many reconnecting clients don't retry in lockstep.
+fn jittered(delay: Duration) -> Duration {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    let spread_ms = (delay.as_millis() as u64 / 5).max(1);
+    let offset_ms = (nanos % (2 * spread_ms + 1)) as i64 - spread_ms as i64;
+    let millis = (delay.as_millis() as i64 + offset_ms).max(0) as u64;
+    Duration::from_millis(millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Backoff;
+    use std::time::Duration;
+
+    // Jitter is +/-20%, so assert against a tolerance band around the
+    // unjittered value rather than an exact duration.
+    fn assert_within_jitter(actual: Duration, expected: Duration) {
+        let tolerance = (expected.as_millis() as i64 / 5).max(1);
+        let diff = (actual.as_millis() as i64 - expected.as_millis() as i64).abs();
+        assert!(
+            diff <= tolerance,
+            "{:?} not within jitter tolerance of {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn doubles_each_attempt_until_capped() {
+        let mut backoff = Backoff::new(Duration::from_millis(100), Duration::from_secs(10));
+        assert_within_jitter(backoff.next_delay(), Duration::from_millis(100));
+        assert_within_jitter(backoff.next_delay(), Duration::from_millis(200));
+        assert_within_jitter(backoff.next_delay(), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn never_exceeds_cap() {
+        let mut backoff = Backoff::new(Duration::from_millis(250), Duration::from_secs(1));
+        for _ in 0..32 {
+            // The cap bounds the delay before jitter, which may add up to 20%.
+            assert!(backoff.next_delay() <= Duration::from_secs(1) + Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn reset_returns_to_base_delay() {
+        let mut backoff = Backoff::new(Duration::from_millis(100), Duration::from_secs(10));
+        backoff.next_delay();
+        backoff.next_delay();
+        backoff.reset();
+        assert_within_jitter(backoff.next_delay(), Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,156 @@
+/// Minimal slot arena used to hand out stable `usize` tokens for in-flight peers.
+///
+/// Freed slots are recycled through an intrusive free list so long-running churn
+/// doesn't grow the backing `Vec` without bound, and callers get a capacity cap
+/// for free via [`Slab::is_full`].
+pub(crate) struct Slab<T> {
+    entries: Vec<Entry<T>>,
+    free_head: Option<usize>,
+    len: usize,
+    capacity: usize,
+}
+
+enum Entry<T> {
+    Occupied(T),
+    Vacant(Option<usize>),
+}
+
+impl<T> Slab<T> {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            free_head: None,
+            len: 0,
+            capacity,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        self.len >= self.capacity
+    }
+
+    /// Inserts `value`, returning its token, or `None` if the arena is at capacity.
+    pub(crate) fn insert(&mut self, value: T) -> Option<usize> {
+        if self.is_full() {
+            return None;
+        }
+        let token = match self.free_head {
+            Some(idx) => {
+                match self.entries[idx] {
+                    Entry::Vacant(next) => self.free_head = next,
+                    Entry::Occupied(_) => unreachable!("free list pointed at an occupied slot"),
+                }
+                self.entries[idx] = Entry::Occupied(value);
+                idx
+            }
+            None => {
+                self.entries.push(Entry::Occupied(value));
+                self.entries.len() - 1
+            }
+        };
+        self.len += 1;
+        Some(token)
+    }
+
+    /// Removes and returns the value at `token`, freeing the slot for reuse.
+    pub(crate) fn remove(&mut self, token: usize) -> Option<T> {
+        let slot = self.entries.get_mut(token)?;
+        if matches!(slot, Entry::Vacant(_)) {
+            return None;
+        }
+        match std::mem::replace(slot, Entry::Vacant(self.free_head)) {
+            Entry::Occupied(value) => {
+                self.free_head = Some(token);
+                self.len -= 1;
+                Some(value)
+            }
+            Entry::Vacant(_) => unreachable!("checked above"),
+        }
+    }
+
+    pub(crate) fn get(&self, token: usize) -> Option<&T> {
+        match self.entries.get(token)? {
+            Entry::Occupied(value) => Some(value),
+            Entry::Vacant(_) => None,
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, token: usize) -> Option<&mut T> {
+        match self.entries.get_mut(token)? {
+            Entry::Occupied(value) => Some(value),
+            Entry::Vacant(_) => None,
+        }
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.entries.iter().enumerate().filter_map(|(token, entry)| match entry {
+            Entry::Occupied(value) => Some((token, value)),
+            Entry::Vacant(_) => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Slab;
+
+    #[test]
+    fn insert_returns_distinct_tokens_and_tracks_len() {
+        let mut slab = Slab::with_capacity(4);
+        let a = slab.insert("a").unwrap();
+        let b = slab.insert("b").unwrap();
+        assert_ne!(a, b);
+        assert_eq!(slab.len(), 2);
+        assert_eq!(slab.get(a), Some(&"a"));
+        assert_eq!(slab.get(b), Some(&"b"));
+    }
+
+    #[test]
+    fn remove_frees_slot_for_reuse() {
+        let mut slab = Slab::with_capacity(4);
+        let a = slab.insert("a").unwrap();
+        let b = slab.insert("b").unwrap();
+        assert_eq!(slab.remove(a), Some("a"));
+        assert_eq!(slab.len(), 1);
+        assert_eq!(slab.get(a), None);
+
+        let c = slab.insert("c").unwrap();
+        assert_eq!(c, a, "freed slot should be recycled before growing the arena");
+        assert_eq!(slab.get(b), Some(&"b"));
+        assert_eq!(slab.len(), 2);
+    }
+
+    #[test]
+    fn remove_is_idempotent() {
+        let mut slab = Slab::with_capacity(2);
+        let a = slab.insert("a").unwrap();
+        assert_eq!(slab.remove(a), Some("a"));
+        assert_eq!(slab.remove(a), None);
+        assert_eq!(slab.remove(99), None);
+    }
+
+    #[test]
+    fn insert_fails_once_at_capacity() {
+        let mut slab = Slab::with_capacity(2);
+        assert!(slab.insert("a").is_some());
+        assert!(slab.insert("b").is_some());
+        assert!(slab.is_full());
+        assert_eq!(slab.insert("c"), None);
+        assert_eq!(slab.len(), 2);
+    }
+
+    #[test]
+    fn iter_yields_only_occupied_slots_with_their_tokens() {
+        let mut slab = Slab::with_capacity(4);
+        let a = slab.insert("a").unwrap();
+        let b = slab.insert("b").unwrap();
+        slab.remove(a);
+
+        let remaining: Vec<_> = slab.iter().collect();
+        assert_eq!(remaining, vec![(b, &"b")]);
+    }
+}